@@ -0,0 +1,313 @@
+//! Runtime configuration, populated from CLI flags and environment
+//! variables (as pict-rs does with `Config::from_args`), so the agent can
+//! target different models and project layouts without recompiling.
+
+use serde::Deserialize;
+use std::{fs, path::Path};
+
+/// A single `(file_name, prompt)` pair describing one file the LLM should
+/// produce.
+pub type Component = (String, String);
+
+/// Which [`crate::store::Store`] implementation to construct.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoreBackend {
+    /// Write to the local filesystem (`FileStore`). The default.
+    Local,
+    /// Write to an S3-compatible bucket (`ObjectStore`), built only when the
+    /// `s3` feature is compiled in.
+    S3,
+}
+
+/// Bucket and credentials for the `s3` store backend.
+#[derive(Debug, Clone, Default)]
+pub struct S3Config {
+    pub bucket: Option<String>,
+    pub region: Option<String>,
+    pub endpoint: Option<String>,
+    pub access_key: Option<String>,
+    pub secret_key: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub model: String,
+    pub ollama_url: String,
+    pub bind: String,
+    pub max_iterations: u32,
+    pub components: Vec<Component>,
+    pub step_deadline_secs: u64,
+    pub job_deadline_secs: u64,
+    pub store_backend: StoreBackend,
+    pub s3: S3Config,
+}
+
+#[derive(Deserialize)]
+struct ComponentSpec {
+    file_name: String,
+    prompt: String,
+}
+
+#[derive(Deserialize)]
+struct ComponentsManifest {
+    components: Vec<ComponentSpec>,
+}
+
+impl Config {
+    /// Build a `Config` from command-line arguments, falling back to
+    /// environment variables and finally these built-in defaults.
+    ///
+    /// Recognized flags: `--model`, `--ollama-url`, `--bind`,
+    /// `--max-iterations`, `--components <file>` (a TOML or JSON manifest
+    /// of `{ file_name, prompt }` entries), `--step-deadline-secs`,
+    /// `--job-deadline-secs`, `--store-backend <local|s3>`, and, when using
+    /// the `s3` backend, `--s3-bucket`, `--s3-region`, `--s3-endpoint`,
+    /// `--s3-access-key`, `--s3-secret-key`.
+    pub fn from_args() -> Self {
+        Self::from_args_iter(std::env::args().skip(1))
+    }
+
+    fn from_args_iter(args: impl Iterator<Item = String>) -> Self {
+        let mut model = env_or("MODEL_NAME", "llama3.2");
+        let mut ollama_url = env_or("OLLAMA_URL", "http://localhost:11434");
+        let mut bind = env_or("BIND_ADDR", "0.0.0.0:8080");
+        let mut max_iterations: u32 = env_or("MAX_ITERATIONS", "50").parse().unwrap_or(50);
+        let mut components_file: Option<String> = std::env::var("COMPONENTS_FILE").ok();
+        let mut step_deadline_secs: u64 =
+            env_or("STEP_DEADLINE_SECS", "120").parse().unwrap_or(120);
+        let mut job_deadline_secs: u64 =
+            env_or("JOB_DEADLINE_SECS", "1800").parse().unwrap_or(1800);
+        let mut store_backend = match env_or("STORE_BACKEND", "local").as_str() {
+            "s3" => StoreBackend::S3,
+            _ => StoreBackend::Local,
+        };
+        let mut s3 = S3Config {
+            bucket: std::env::var("S3_BUCKET").ok(),
+            region: std::env::var("S3_REGION").ok(),
+            endpoint: std::env::var("S3_ENDPOINT").ok(),
+            access_key: std::env::var("S3_ACCESS_KEY").ok(),
+            secret_key: std::env::var("S3_SECRET_KEY").ok(),
+        };
+
+        let mut args = args.peekable();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--model" => model = args.next().unwrap_or(model),
+                "--ollama-url" => ollama_url = args.next().unwrap_or(ollama_url),
+                "--bind" => bind = args.next().unwrap_or(bind),
+                "--max-iterations" => {
+                    if let Some(value) = args.next().and_then(|v| v.parse().ok()) {
+                        max_iterations = value;
+                    }
+                }
+                "--components" => components_file = args.next(),
+                "--step-deadline-secs" => {
+                    if let Some(value) = args.next().and_then(|v| v.parse().ok()) {
+                        step_deadline_secs = value;
+                    }
+                }
+                "--job-deadline-secs" => {
+                    if let Some(value) = args.next().and_then(|v| v.parse().ok()) {
+                        job_deadline_secs = value;
+                    }
+                }
+                "--store-backend" => {
+                    store_backend = match args.next().as_deref() {
+                        Some("s3") => StoreBackend::S3,
+                        _ => StoreBackend::Local,
+                    }
+                }
+                "--s3-bucket" => s3.bucket = args.next(),
+                "--s3-region" => s3.region = args.next(),
+                "--s3-endpoint" => s3.endpoint = args.next(),
+                "--s3-access-key" => s3.access_key = args.next(),
+                "--s3-secret-key" => s3.secret_key = args.next(),
+                _ => {}
+            }
+        }
+
+        let components = components_file
+            .as_deref()
+            .map(Path::new)
+            .and_then(|path| match load_components(path) {
+                Ok(components) => Some(components),
+                Err(e) => {
+                    eprintln!(
+                        "Failed to load components manifest {}: {}. Falling back to defaults.",
+                        path.display(),
+                        e
+                    );
+                    None
+                }
+            })
+            .unwrap_or_else(default_components);
+
+        Config {
+            model,
+            ollama_url,
+            bind,
+            max_iterations,
+            components,
+            step_deadline_secs,
+            job_deadline_secs,
+            store_backend,
+            s3,
+        }
+    }
+}
+
+fn env_or(key: &str, default: &str) -> String {
+    std::env::var(key).unwrap_or_else(|_| default.to_string())
+}
+
+fn load_components(path: &Path) -> Result<Vec<Component>, String> {
+    let contents = fs::read_to_string(path).map_err(|e| e.to_string())?;
+
+    let manifest: ComponentsManifest = if path.extension().and_then(|e| e.to_str()) == Some("json")
+    {
+        serde_json::from_str(&contents).map_err(|e| e.to_string())?
+    } else {
+        toml::from_str(&contents).map_err(|e| e.to_string())?
+    };
+
+    Ok(manifest
+        .components
+        .into_iter()
+        .map(|c| (c.file_name, c.prompt))
+        .collect())
+}
+
+/// The original fixed Flask project layout, kept as the default when no
+/// `--components` manifest is given.
+fn default_components() -> Vec<Component> {
+    [
+        ("app.py", "Create or update the main Flask application file (app.py) with necessary imports and app initialization."),
+        ("config.py", "Create or update the configuration file (config.py) with any necessary settings."),
+        ("models.py", "Create or update the models file (models.py) with any database models the application might need."),
+        ("routes.py", "Create or update the routes file (routes.py) with all the necessary route handlers."),
+        ("forms.py", "Create or update the forms file (forms.py) with any form classes the application might use."),
+        ("templates/index.html", "Create or update the HTML template for the main page."),
+        ("templates/layout.html", "Create or update the base layout HTML template."),
+        ("static/style.css", "Create or update the CSS file for styling the application."),
+        ("requirements.txt", "Create or update the requirements.txt file listing all necessary Python packages."),
+    ]
+    .into_iter()
+    .map(|(file_name, prompt)| (file_name.to_string(), prompt.to_string()))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(flags: &[&str]) -> impl Iterator<Item = String> {
+        flags
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn from_args_iter_applies_cli_flags_over_defaults() {
+        let config = Config::from_args_iter(args(&[
+            "--model",
+            "mistral",
+            "--bind",
+            "127.0.0.1:9000",
+            "--max-iterations",
+            "7",
+            "--step-deadline-secs",
+            "30",
+            "--job-deadline-secs",
+            "600",
+        ]));
+
+        assert_eq!(config.model, "mistral");
+        assert_eq!(config.bind, "127.0.0.1:9000");
+        assert_eq!(config.max_iterations, 7);
+        assert_eq!(config.step_deadline_secs, 30);
+        assert_eq!(config.job_deadline_secs, 600);
+        assert_eq!(config.components, default_components());
+        assert_eq!(config.store_backend, StoreBackend::Local);
+    }
+
+    #[test]
+    fn from_args_iter_falls_back_to_defaults_for_unrecognized_numbers() {
+        let config = Config::from_args_iter(args(&["--max-iterations", "not-a-number"]));
+        assert_eq!(config.max_iterations, 50);
+    }
+
+    #[test]
+    fn from_args_iter_parses_the_s3_store_backend_and_credentials() {
+        let config = Config::from_args_iter(args(&[
+            "--store-backend",
+            "s3",
+            "--s3-bucket",
+            "generated-projects",
+            "--s3-region",
+            "us-east-1",
+            "--s3-access-key",
+            "AKIA...",
+            "--s3-secret-key",
+            "secret",
+        ]));
+
+        assert_eq!(config.store_backend, StoreBackend::S3);
+        assert_eq!(config.s3.bucket.as_deref(), Some("generated-projects"));
+        assert_eq!(config.s3.region.as_deref(), Some("us-east-1"));
+    }
+
+    #[test]
+    fn load_components_parses_a_toml_manifest() {
+        let path = std::env::temp_dir().join("config_test_components.toml");
+        fs::write(
+            &path,
+            r#"
+            [[components]]
+            file_name = "app.py"
+            prompt = "Write app.py"
+
+            [[components]]
+            file_name = "routes.py"
+            prompt = "Write routes.py"
+            "#,
+        )
+        .unwrap();
+
+        let components = load_components(&path).unwrap();
+        assert_eq!(
+            components,
+            vec![
+                ("app.py".to_string(), "Write app.py".to_string()),
+                ("routes.py".to_string(), "Write routes.py".to_string()),
+            ]
+        );
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn load_components_parses_a_json_manifest() {
+        let path = std::env::temp_dir().join("config_test_components.json");
+        fs::write(
+            &path,
+            r#"{"components": [{"file_name": "app.py", "prompt": "Write app.py"}]}"#,
+        )
+        .unwrap();
+
+        let components = load_components(&path).unwrap();
+        assert_eq!(
+            components,
+            vec![("app.py".to_string(), "Write app.py".to_string())]
+        );
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn load_components_reports_an_error_for_a_missing_file() {
+        let path = Path::new("does/not/exist.toml");
+        assert!(load_components(path).is_err());
+    }
+}
@@ -1,16 +1,32 @@
+mod config;
+mod queue;
+mod repo;
+mod store;
+mod validate;
+
 use actix_files as afs;
-use actix_web::{rt::spawn, web, App, HttpResponse, HttpServer, Responder};
+use actix_web::{web, App, HttpResponse, HttpServer, Responder};
+use dashmap::DashMap;
 use ollama_rs::{generation::completion::request::GenerationRequest, Ollama};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
-use std::{
-    fs,
-    path::Path,
-    sync::{Arc, Mutex},
-};
+use std::{fs, path::Path, sync::Arc};
+
+use futures_util::{stream, StreamExt};
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream};
+
+use config::{Config, StoreBackend};
+use queue::{job_dir, Job, JobId, JobQueue};
+use repo::ProjectRepo;
+use store::{FileStore, Store};
 
-const MODEL_NAME: &str = "llama3.2";
+/// Maximum number of generation jobs allowed to run at the same time.
+const MAX_CONCURRENT_JOBS: usize = 4;
+
+/// Maximum number of times a Python file is re-prompted after a compile failure.
+const MAX_REPAIR_ATTEMPTS: u32 = 3;
 
 #[derive(Clone, Serialize, Deserialize, Default)]
 struct Progress {
@@ -19,6 +35,11 @@ struct Progress {
     max_iteration: u32,
     output: String,
     completed: bool,
+    /// Deadline given to the step currently running, so the progress route
+    /// can report how much of it is left.
+    step_deadline_secs: u64,
+    #[serde(skip)]
+    step_started_at: Option<std::time::Instant>,
 }
 
 #[derive(Deserialize)]
@@ -26,22 +47,15 @@ struct UserInput {
     user_input: String,
 }
 
-async fn home(progress: web::Data<Mutex<Progress>>) -> impl Responder {
-    // Reset the progress
-    {
-        let mut progress_guard = progress.lock().unwrap();
-        *progress_guard = Progress::default();
-    }
-
+async fn home(store: web::Data<Arc<dyn Store>>) -> impl Responder {
     let index_path = Path::new("templates/index.html");
 
-    if index_path.exists() {
-        match fs::read_to_string(index_path) {
+    match store.read(index_path).await {
+        Ok(bytes) => match String::from_utf8(bytes) {
             Ok(contents) => HttpResponse::Ok().content_type("text/html").body(contents),
-            Err(_) => HttpResponse::InternalServerError().body("Error reading index.html"),
-        }
-    } else {
-        HttpResponse::Ok().content_type("text/html").body(
+            Err(_) => HttpResponse::InternalServerError().body("index.html is not valid UTF-8"),
+        },
+        Err(_) => HttpResponse::Ok().content_type("text/html").body(
             r#"
             <h1>Flask App Generator</h1>
             <form method="post">
@@ -50,143 +64,355 @@ async fn home(progress: web::Data<Mutex<Progress>>) -> impl Responder {
                 <input type="submit" value="Generate/Update Flask App">
             </form>
             "#,
-        )
+        ),
+    }
+}
+
+// Progress route handler: looks up a single job's progress by id.
+async fn get_progress(queue: web::Data<JobQueue>, job_id: web::Path<JobId>) -> impl Responder {
+    match queue.get(&job_id) {
+        Some(progress) => {
+            let step_remaining_secs = progress.step_started_at.map(|started| {
+                progress
+                    .step_deadline_secs
+                    .saturating_sub(started.elapsed().as_secs())
+            });
+            HttpResponse::Ok().json(json!({
+                "status": progress.status,
+                "iteration": progress.iteration,
+                "max_iteration": progress.max_iteration,
+                "output": progress.output,
+                "completed": progress.completed,
+                "step_deadline_secs": progress.step_deadline_secs,
+                "step_remaining_secs": step_remaining_secs,
+            }))
+        }
+        None => HttpResponse::NotFound().body("No such job"),
     }
 }
 
-// Progress route handler
-async fn get_progress(progress: web::Data<Mutex<Progress>>) -> impl Responder {
-    let progress = progress.lock().unwrap();
-    web::Json(progress.clone())
+/// Frames a line of progress output as an SSE `data:` event.
+fn sse_frame(line: &str) -> web::Bytes {
+    web::Bytes::from(format!("data: {}\n\n", line.replace('\n', "\ndata: ")))
+}
+
+// Streams progress deltas for a job as Server-Sent Events, so the client
+// gets incremental output instead of re-fetching and re-rendering the
+// whole accumulated log every couple of seconds.
+async fn stream_events(queue: web::Data<JobQueue>, job_id: web::Path<JobId>) -> impl Responder {
+    // Snapshot the output produced so far *before* subscribing, so a line
+    // pushed in between can't end up both in the snapshot and delivered
+    // live, which would render it twice.
+    let snapshot = queue.get(&job_id).map(|p| p.output);
+
+    match queue.subscribe(&job_id) {
+        Some(receiver) => {
+            // Replay everything produced so far, so a client that opens the
+            // stream after the job has already started doesn't see a blank
+            // pane until the next line comes in.
+            let snapshot = snapshot.unwrap_or_default();
+            let backlog =
+                stream::once(async move { Ok::<_, actix_web::Error>(sse_frame(&snapshot)) });
+
+            let live = BroadcastStream::new(receiver).map(|message| match message {
+                Ok(line) => Ok::<_, actix_web::Error>(sse_frame(&line)),
+                Err(BroadcastStreamRecvError::Lagged(skipped)) => Ok(sse_frame(&format!(
+                    "[missed {} progress update(s) because the client fell behind; output may be out of sync]",
+                    skipped
+                ))),
+            });
+
+            HttpResponse::Ok()
+                .content_type("text/event-stream")
+                .streaming(backlog.chain(live))
+        }
+        None => HttpResponse::NotFound().body("No such job"),
+    }
+}
+
+// Lists the per-step commits recorded for a job, newest first.
+async fn list_commits(job_id: web::Path<JobId>) -> impl Responder {
+    let dir = job_dir(&job_id);
+    match ProjectRepo::open_or_init(&dir) {
+        Ok(repo) => match repo.list_commits() {
+            Ok(commits) => {
+                let commits: Vec<_> = commits
+                    .into_iter()
+                    .map(|c| json!({"sha": c.sha, "message": c.message}))
+                    .collect();
+                HttpResponse::Ok().json(commits)
+            }
+            Err(e) => {
+                HttpResponse::InternalServerError().body(format!("Error listing commits: {}", e))
+            }
+        },
+        Err(e) => HttpResponse::NotFound().body(format!("No repository for job: {}", e)),
+    }
+}
+
+// Hard-resets a job's working tree back to a previously recorded commit.
+async fn reset_to_commit(path: web::Path<(JobId, String)>) -> impl Responder {
+    let (job_id, sha) = path.into_inner();
+    let dir = job_dir(&job_id);
+    match ProjectRepo::open_or_init(&dir) {
+        Ok(repo) => match repo.reset_hard(&sha) {
+            Ok(()) => HttpResponse::Ok().body(format!("Reset to {}", sha)),
+            Err(e) => HttpResponse::BadRequest().body(format!("Error resetting to {}: {}", sha, e)),
+        },
+        Err(e) => HttpResponse::NotFound().body(format!("No repository for job: {}", e)),
+    }
+}
+
+/// Construct the configured [`Store`], failing fast if `s3` was selected but
+/// this binary wasn't compiled with the `s3` feature.
+fn build_store(config: &Config) -> Arc<dyn Store> {
+    match config.store_backend {
+        StoreBackend::Local => Arc::new(FileStore::new(".")),
+        StoreBackend::S3 => {
+            #[cfg(feature = "s3")]
+            {
+                let s3_config = store::ObjectStoreConfig {
+                    bucket: config.s3.bucket.clone().expect("--s3-bucket is required"),
+                    region: config.s3.region.clone().expect("--s3-region is required"),
+                    endpoint: config.s3.endpoint.clone(),
+                    access_key: config
+                        .s3
+                        .access_key
+                        .clone()
+                        .expect("--s3-access-key is required"),
+                    secret_key: config
+                        .s3
+                        .secret_key
+                        .clone()
+                        .expect("--s3-secret-key is required"),
+                };
+                Arc::new(store::ObjectStore::new(s3_config))
+            }
+            #[cfg(not(feature = "s3"))]
+            {
+                panic!(
+                    "--store-backend s3 was requested but this binary was built without the \
+                    `s3` feature; rebuild with `--features s3`"
+                );
+            }
+        }
+    }
 }
 
 // Main function to run the application
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    let progress = web::Data::new(Mutex::new(Progress::default()));
+    let config = Arc::new(Config::from_args());
+    let bind_addr = config.bind.clone();
+
+    let store: web::Data<Arc<dyn Store>> = web::Data::new(build_store(&config));
+    let queue = web::Data::new(JobQueue::new(
+        MAX_CONCURRENT_JOBS,
+        store.as_ref().clone(),
+        config.clone(),
+    ));
 
     HttpServer::new(move || {
         App::new()
-            .app_data(progress.clone())
+            .app_data(queue.clone())
+            .app_data(store.clone())
             .service(afs::Files::new("/static", "static").show_files_listing())
             .service(
                 web::resource("/")
                     .route(web::get().to(home))
                     .route(web::post().to(handle_post)),
             )
-            .service(web::resource("/progress").route(web::get().to(get_progress)))
+            .service(web::resource("/progress/{job_id}").route(web::get().to(get_progress)))
+            .service(web::resource("/events/{job_id}").route(web::get().to(stream_events)))
+            .service(web::resource("/jobs/{job_id}/commits").route(web::get().to(list_commits)))
+            .service(
+                web::resource("/jobs/{job_id}/reset/{sha}").route(web::post().to(reset_to_commit)),
+            )
     })
-    .bind("0.0.0.0:8080")?
+    .bind(bind_addr)?
     .run()
     .await
 }
 
-// Function to handle POST requests
-async fn handle_post(
-    form: web::Form<UserInput>,
-    progress: web::Data<Mutex<Progress>>,
-) -> impl Responder {
-    let user_input = form.user_input.clone();
-    let progress_clone = Arc::new(progress.clone());
-
-    // Start the main loop in a background task
-    spawn(async move {
-        run_main_loop(user_input, progress_clone).await;
-    });
+// Function to handle POST requests: enqueues a job and hands back its id.
+async fn handle_post(form: web::Form<UserInput>, queue: web::Data<JobQueue>) -> impl Responder {
+    let job_id = queue.submit(form.user_input.clone());
 
-    HttpResponse::Ok().body(
+    HttpResponse::Ok().body(format!(
         r#"
         <h1>Progress</h1>
+        <p>Job id: {job_id}</p>
         <pre id="progress" style="white-space: pre-wrap; word-wrap: break-word;"></pre>
         <script>
-            function updateProgress() {
-                fetch('/progress')
-                .then(response => response.json())
-                .then(data => {
-                    document.getElementById('progress').innerHTML = data.output;
-                    if (data.completed) {
-                        // Redirect to the main page after a short delay
-                        setTimeout(() => window.location.href = '/', 3000);
-                    } else {
-                        setTimeout(updateProgress, 2000);
-                    }
-                });
-            }
-            updateProgress();
+            var progressEl = document.getElementById('progress');
+            var source = new EventSource('/events/{job_id}');
+            source.onmessage = function(event) {{
+                progressEl.innerHTML += event.data;
+                if (event.data.includes('Redirecting to main page')) {{
+                    source.close();
+                    setTimeout(() => window.location.href = '/', 3000);
+                }}
+            }};
+            source.onerror = function() {{
+                // The stream drops once the browser reconnect backoff gives
+                // up; fall back to a single poll so the page isn't stuck
+                // blank if the job already finished before we connected.
+                source.close();
+                fetch('/progress/{job_id}')
+                    .then(response => response.json())
+                    .then(data => {{
+                        progressEl.innerHTML = data.output;
+                        if (data.completed) {{
+                            setTimeout(() => window.location.href = '/', 3000);
+                        }}
+                    }});
+            }};
         </script>
         "#,
-    )
+        job_id = job_id
+    ))
 }
 
-async fn run_main_loop(user_input: String, progress: Arc<web::Data<Mutex<Progress>>>) {
-    let ollama = Ollama::default();
+async fn run_main_loop(
+    job: Job,
+    progress_table: Arc<DashMap<JobId, Progress>>,
+    store: Arc<dyn Store>,
+    config: Arc<Config>,
+    events: broadcast::Sender<String>,
+) {
+    let ollama = ollama_client(&config.ollama_url);
 
-    // Use a fixed directory name for the Flask application
-    let dir_name = "flask_app";
-    let app_dir = Path::new(dir_name);
+    let app_dir = job.target_dir.as_path();
+    let user_input = job.user_input;
 
     // Create the directory if it doesn't exist
     if !app_dir.exists() {
-        if let Err(e) = fs::create_dir(app_dir) {
-            let mut progress_guard = progress.lock().unwrap();
-            progress_guard.output += &format!("Error creating directory: {}\n", e);
+        if let Err(e) = fs::create_dir_all(app_dir) {
+            push_output(
+                &progress_table,
+                &events,
+                &job.id,
+                format!("Error creating directory: {}\n", e),
+            );
             return;
         }
     }
 
     // Initial update
     {
-        let mut progress_guard = progress.lock().unwrap();
-        progress_guard.status = "running".to_string();
-        progress_guard.iteration = 0;
-        progress_guard.output = format!("Using application directory: {}\n", dir_name);
-        progress_guard.completed = false;
+        let line = format!("Using application directory: {}\n", app_dir.display());
+        let mut progress = progress_table.get_mut(&job.id).unwrap();
+        progress.status = "running".to_string();
+        progress.iteration = 0;
+        progress.max_iteration = config.max_iterations;
+        progress.output = line.clone();
+        progress.completed = false;
+        drop(progress);
+        let _ = events.send(line);
     }
 
+    let project_repo = match ProjectRepo::open_or_init(app_dir) {
+        Ok(repo) => repo,
+        Err(e) => {
+            push_output(
+                &progress_table,
+                &events,
+                &job.id,
+                format!("Error opening project repository: {}\n", e),
+            );
+            return;
+        }
+    };
+
     let mut history = json!({
         "iterations": [],
-        "app_directory": dir_name
+        "app_directory": app_dir
     });
 
-    // Define the structure of the Flask application
-    let components = vec![
-        ("app.py", "Create or update the main Flask application file (app.py) with necessary imports and app initialization."),
-        ("config.py", "Create or update the configuration file (config.py) with any necessary settings."),
-        ("models.py", "Create or update the models file (models.py) with any database models the application might need."),
-        ("routes.py", "Create or update the routes file (routes.py) with all the necessary route handlers."),
-        ("forms.py", "Create or update the forms file (forms.py) with any form classes the application might use."),
-        ("templates/index.html", "Create or update the HTML template for the main page."),
-        ("templates/layout.html", "Create or update the base layout HTML template."),
-        ("static/style.css", "Create or update the CSS file for styling the application."),
-        ("requirements.txt", "Create or update the requirements.txt file listing all necessary Python packages.")
-    ];
+    // The project layout: a fixed Flask app by default, or whatever
+    // `--components` manifest the operator supplied.
+    let components = &config.components;
+
+    // Overall budget for the job; checked between steps so a run that's
+    // burning through repair attempts doesn't wedge a worker forever.
+    let job_deadline = tokio::time::Duration::from_secs(config.job_deadline_secs);
+    let job_started_at = tokio::time::Instant::now();
 
     // Initial prompt to set the context for the LLM
     let initial_prompt = format!(
-        "You are a Python Flask expert. Your task is to help build or update a multi-file Flask v3 web application based on the following request: '{}'. 
-        You will be asked to generate or modify Python code for different components of the application. 
+        "You are a Python Flask expert. Your task is to help build or update a multi-file Flask v3 web application based on the following request: '{}'.
+        You will be asked to generate or modify Python code for different components of the application.
         Provide only the code or content, without any explanations or Markdown formatting. Each response should be a complete, valid file for the specified component.
         If the file already exists, incorporate the new requirements while preserving existing functionality.",
         user_input
     );
 
+    let mut job_timed_out = false;
+
     for (i, (file_name, component_prompt)) in components.iter().enumerate() {
+        if job_started_at.elapsed() >= job_deadline {
+            push_output(
+                &progress_table,
+                &events,
+                &job.id,
+                format!(
+                    "\nJob deadline of {}s exceeded before {} could be generated; stopping.\n",
+                    config.job_deadline_secs, file_name
+                ),
+            );
+            job_timed_out = true;
+            break;
+        }
+
         tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
 
         // Update progress
         {
-            let mut progress_guard = progress.lock().unwrap();
-            progress_guard.iteration = i as u32 + 1;
-            progress_guard.output += &format!("\nGenerating or updating {}...\n", file_name);
+            let line = format!("\nGenerating or updating {}...\n", file_name);
+            let mut progress = progress_table.get_mut(&job.id).unwrap();
+            progress.iteration = i as u32 + 1;
+            progress.output += &line;
+            progress.step_deadline_secs = config.step_deadline_secs;
+            progress.step_started_at = Some(tokio::time::Instant::now().into_std());
+            drop(progress);
+            let _ = events.send(line);
         }
 
-        // LLM interaction
+        // LLM interaction. This still waits for the whole response rather
+        // than streaming Ollama's token-by-token output: the repair loop
+        // below needs a complete file to run `py_compile` against, so
+        // there's nothing useful to push to the SSE channel mid-generation.
+        //
+        // Each generation is bounded by `step_deadline`, so a stuck Ollama
+        // call fails the step instead of wedging the job forever.
         let prompt = format!("{}\n\n{}", initial_prompt, component_prompt);
-        let request = GenerationRequest::new(MODEL_NAME.to_string(), prompt);
+        let request = GenerationRequest::new(config.model.clone(), prompt);
+        let step_deadline = tokio::time::Duration::from_secs(config.step_deadline_secs);
+
+        let generation = match with_step_deadline(step_deadline, ollama.generate(request)).await {
+            Ok(result) => result,
+            Err(_) => {
+                push_output(
+                    &progress_table,
+                    &events,
+                    &job.id,
+                    format!(
+                        "Timed out after {}s waiting for the model to generate {}; skipping.\n",
+                        config.step_deadline_secs, file_name
+                    ),
+                );
+                history["iterations"].as_array_mut().unwrap().push(json!({
+                    "step": i + 1,
+                    "file_name": file_name,
+                    "timed_out": true,
+                }));
+                continue;
+            }
+        };
 
-        match ollama.generate(request).await {
+        match generation {
             Ok(response) => {
-                let llm_output = clean_llm_output(&response.response);
+                let mut llm_output = clean_llm_output(&response.response);
 
                 // Create the full path for the file
                 let file_path = app_dir.join(file_name);
@@ -194,13 +420,112 @@ async fn run_main_loop(user_input: String, progress: Arc<web::Data<Mutex<Progres
                 // Ensure parent directory exists (for templates and static files)
                 if let Some(parent) = file_path.parent() {
                     if let Err(e) = fs::create_dir_all(parent) {
-                        let mut progress_guard = progress.lock().unwrap();
-                        progress_guard.output +=
-                            &format!("Error creating directory {}: {}\n", parent.display(), e);
+                        push_output(
+                            &progress_table,
+                            &events,
+                            &job.id,
+                            format!("Error creating directory {}: {}\n", parent.display(), e),
+                        );
                         continue;
                     }
                 }
 
+                // Python files only get persisted once they compile (or run
+                // out of repair attempts); re-prompt the LLM with the exact
+                // compiler error on failure.
+                let mut repair_attempts = Vec::new();
+                if file_name.ends_with(".py") {
+                    for attempt in 1..=MAX_REPAIR_ATTEMPTS {
+                        if let Err(e) = fs::write(&file_path, &llm_output) {
+                            push_output(
+                                &progress_table,
+                                &events,
+                                &job.id,
+                                format!("Error writing {} for validation: {}\n", file_name, e),
+                            );
+                            break;
+                        }
+
+                        match validate::check_python_syntax(&file_path).await {
+                            Ok(()) => break,
+                            Err(compile_err) => {
+                                let repair = validate::repair_prompt(
+                                    component_prompt,
+                                    &llm_output,
+                                    &compile_err.stderr,
+                                );
+                                let repair_request =
+                                    GenerationRequest::new(config.model.clone(), repair);
+
+                                // Each repair attempt gets its own fresh
+                                // deadline window, so `step_remaining_secs`
+                                // doesn't stay pinned at 0 for the whole
+                                // repair sequence once the original
+                                // generation's window elapses.
+                                if let Some(mut progress) = progress_table.get_mut(&job.id) {
+                                    progress.step_deadline_secs = config.step_deadline_secs;
+                                    progress.step_started_at =
+                                        Some(tokio::time::Instant::now().into_std());
+                                }
+
+                                match with_step_deadline(
+                                    step_deadline,
+                                    ollama.generate(repair_request),
+                                )
+                                .await
+                                {
+                                    Ok(Ok(repair_response)) => {
+                                        llm_output = clean_llm_output(&repair_response.response);
+
+                                        let fixed = fs::write(&file_path, &llm_output).is_ok()
+                                            && validate::check_python_syntax(&file_path)
+                                                .await
+                                                .is_ok();
+                                        repair_attempts.push(json!({
+                                            "attempt": attempt,
+                                            "error": compile_err.stderr,
+                                            "fixed": fixed,
+                                        }));
+                                        if fixed {
+                                            break;
+                                        }
+                                    }
+                                    Ok(Err(e)) => {
+                                        repair_attempts.push(json!({
+                                            "attempt": attempt,
+                                            "error": compile_err.stderr,
+                                            "fixed": false,
+                                        }));
+                                        push_output(
+                                            &progress_table,
+                                            &events,
+                                            &job.id,
+                                            format!("Error repairing {}: {}\n", file_name, e),
+                                        );
+                                    }
+                                    Err(_) => {
+                                        repair_attempts.push(json!({
+                                            "attempt": attempt,
+                                            "error": compile_err.stderr,
+                                            "fixed": false,
+                                            "timed_out": true,
+                                        }));
+                                        push_output(
+                                            &progress_table,
+                                            &events,
+                                            &job.id,
+                                            format!(
+                                                "Timed out after {}s repairing {}; giving up on this attempt.\n",
+                                                config.step_deadline_secs, file_name
+                                            ),
+                                        );
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
                 // Create or update the file with cleaned LLM output
                 let file_result = match fs::write(&file_path, &llm_output) {
                     Ok(_) => format!("Created/Updated file: {}", file_path.display()),
@@ -211,50 +536,140 @@ async fn run_main_loop(user_input: String, progress: Arc<web::Data<Mutex<Progres
                     ),
                 };
 
-                // Update progress with LLM output and file operation result
-                {
-                    let mut progress_guard = progress.lock().unwrap();
-                    progress_guard.output +=
-                        &format!("LLM Output for {}:\n{}\n", file_name, llm_output);
-                    progress_guard.output += &format!("File operation: {}\n", file_result);
+                // Commit this step's file so the history is rollback-able.
+                let commit_message = format!("step {}: {}", i + 1, file_name);
+                let commit_sha =
+                    match project_repo.commit_file(Path::new(file_name), &commit_message) {
+                        Ok(sha) => sha,
+                        Err(e) => format!("<commit failed: {}>", e),
+                    };
+
+                // Mirror the file into the configured store (local disk by
+                // default, or an S3-compatible bucket), keyed by job id.
+                if let Err(e) = store.write(&file_path, llm_output.as_bytes()).await {
+                    push_output(
+                        &progress_table,
+                        &events,
+                        &job.id,
+                        format!("Error mirroring {} to store: {}\n", file_name, e),
+                    );
                 }
 
+                // Update progress with LLM output and file operation result
+                push_output(
+                    &progress_table,
+                    &events,
+                    &job.id,
+                    format!(
+                        "LLM Output for {}:\n{}\nFile operation: {}\nCommitted as {}\n",
+                        file_name, llm_output, file_result, commit_sha
+                    ),
+                );
+
                 // Update history
                 history["iterations"].as_array_mut().unwrap().push(json!({
                     "step": i + 1,
                     "file_name": file_name,
                     "file_operation": file_result,
-                    "file_content": llm_output
+                    "commit_sha": commit_sha,
+                    "repair_attempts": repair_attempts
                 }));
             }
             Err(e) => {
-                let error_msg = format!("Error in LLM interaction: {}", e);
-                let mut progress_guard = progress.lock().unwrap();
-                progress_guard.output += &format!("{}\n", error_msg);
+                push_output(
+                    &progress_table,
+                    &events,
+                    &job.id,
+                    format!("Error in LLM interaction: {}\n", e),
+                );
             }
         }
     }
 
-    // Log history to file
+    // Log history to file, both locally and in the configured store.
     let history_file_path = app_dir.join("generation_history.json");
-    if let Err(e) = fs::write(
-        &history_file_path,
-        serde_json::to_string_pretty(&history).unwrap(),
-    ) {
-        let mut progress_guard = progress.lock().unwrap();
-        progress_guard.output += &format!("Error writing history file: {}\n", e);
+    let history_bytes = serde_json::to_string_pretty(&history).unwrap();
+    if let Err(e) = fs::write(&history_file_path, &history_bytes) {
+        push_output(
+            &progress_table,
+            &events,
+            &job.id,
+            format!("Error writing history file: {}\n", e),
+        );
+    }
+    if let Err(e) = store
+        .write(&history_file_path, history_bytes.as_bytes())
+        .await
+    {
+        push_output(
+            &progress_table,
+            &events,
+            &job.id,
+            format!("Error mirroring history file to store: {}\n", e),
+        );
     }
 
     // Final update
     {
-        let mut progress_guard = progress.lock().unwrap();
-        progress_guard.status = "completed".to_string();
-        progress_guard.completed = true;
-        progress_guard.output += &format!(
-            "\nFlask application generation/update completed! Files are in the '{}' directory.\n",
-            dir_name
-        );
-        progress_guard.output += "Redirecting to main page in 3 seconds...";
+        let line = if job_timed_out {
+            "\nJob timed out before all components were generated.\nRedirecting to main page in 3 seconds...".to_string()
+        } else {
+            format!(
+                "\nFlask application generation/update completed! Files are in the '{}' directory.\nRedirecting to main page in 3 seconds...",
+                app_dir.display()
+            )
+        };
+        let mut progress = progress_table.get_mut(&job.id).unwrap();
+        progress.status = if job_timed_out {
+            "timed_out".to_string()
+        } else {
+            "completed".to_string()
+        };
+        progress.completed = true;
+        progress.output += &line;
+        drop(progress);
+        let _ = events.send(line);
+    }
+}
+
+/// Appends `line` to a job's progress output and broadcasts it to any live
+/// SSE subscribers.
+fn push_output(
+    progress_table: &DashMap<JobId, Progress>,
+    events: &broadcast::Sender<String>,
+    job_id: &JobId,
+    line: String,
+) {
+    if let Some(mut progress) = progress_table.get_mut(job_id) {
+        progress.output += &line;
+    }
+    let _ = events.send(line);
+}
+
+/// Bounds `fut` by `deadline`, used for both a file's initial generation and
+/// each repair attempt so a stuck Ollama call fails the step instead of
+/// wedging the job forever.
+async fn with_step_deadline<F: std::future::Future>(
+    deadline: tokio::time::Duration,
+    fut: F,
+) -> Result<F::Output, tokio::time::error::Elapsed> {
+    tokio::time::timeout(deadline, fut).await
+}
+
+/// Builds an Ollama client pointed at `ollama_url`, falling back to the
+/// library's default (`http://localhost:11434`) if it doesn't parse.
+fn ollama_client(ollama_url: &str) -> Ollama {
+    match url::Url::parse(ollama_url) {
+        Ok(url) => {
+            let host = format!(
+                "{}://{}",
+                url.scheme(),
+                url.host_str().unwrap_or("localhost")
+            );
+            let port = url.port().unwrap_or(11434);
+            Ollama::new(host, port)
+        }
+        Err(_) => Ollama::default(),
     }
 }
 
@@ -276,4 +691,41 @@ fn clean_llm_output(output: &str) -> String {
     cleaned_lines.join("\n")
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn with_step_deadline_times_out_on_a_future_that_never_resolves() {
+        let deadline = tokio::time::Duration::from_secs(10);
+        let never = std::future::pending::<()>();
+        let handle = tokio::spawn(with_step_deadline(deadline, never));
+
+        tokio::time::advance(tokio::time::Duration::from_secs(11)).await;
 
+        assert!(handle.await.unwrap().is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn with_step_deadline_succeeds_when_the_future_resolves_in_time() {
+        let deadline = tokio::time::Duration::from_secs(10);
+        let fast = tokio::time::sleep(tokio::time::Duration::from_secs(1));
+        let handle = tokio::spawn(with_step_deadline(deadline, fast));
+
+        tokio::time::advance(tokio::time::Duration::from_secs(2)).await;
+
+        assert!(handle.await.unwrap().is_ok());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn job_deadline_elapses_only_after_the_configured_duration() {
+        let job_deadline = tokio::time::Duration::from_secs(30);
+        let job_started_at = tokio::time::Instant::now();
+
+        tokio::time::advance(tokio::time::Duration::from_secs(29)).await;
+        assert!(job_started_at.elapsed() < job_deadline);
+
+        tokio::time::advance(tokio::time::Duration::from_secs(2)).await;
+        assert!(job_started_at.elapsed() >= job_deadline);
+    }
+}
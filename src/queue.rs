@@ -0,0 +1,161 @@
+//! Job queue subsystem: turns the one-shot generator into a worker pool that
+//! can run several generation requests at once without trampling shared state.
+//!
+//! Modeled on pict-rs's `queue` module: each submitted prompt becomes a
+//! [`Job`] with its own working directory, jobs are tracked in a
+//! [`DashMap`] keyed by [`JobId`], and a [`tokio::sync::Semaphore`] caps how
+//! many generations run in parallel.
+
+use dashmap::DashMap;
+use std::{path::PathBuf, sync::Arc, time::Duration};
+use tokio::sync::{broadcast, Semaphore};
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::store::Store;
+use crate::Progress;
+
+/// Ring-buffer size for each job's progress broadcast channel.
+const EVENTS_CHANNEL_CAPACITY: usize = 1024;
+
+/// How long a finished job's `Progress`/events entry is kept around before
+/// being reaped, so a client still polling or reconnecting its `EventSource`
+/// right after completion has time to see the final state.
+const COMPLETED_JOB_RETENTION: Duration = Duration::from_secs(300);
+
+/// Unique identifier for a submitted generation job.
+pub type JobId = Uuid;
+
+/// A single submitted generation request.
+pub struct Job {
+    pub id: JobId,
+    pub user_input: String,
+    pub target_dir: PathBuf,
+}
+
+/// Working directory for a given job id, e.g. `flask_app/<job_id>/`.
+pub fn job_dir(id: &JobId) -> PathBuf {
+    PathBuf::from("flask_app").join(id.to_string())
+}
+
+/// Shared handle to the queue's progress table and concurrency limiter.
+///
+/// Cheap to clone: everything behind an `Arc`.
+#[derive(Clone)]
+pub struct JobQueue {
+    progress: Arc<DashMap<JobId, Progress>>,
+    semaphore: Arc<Semaphore>,
+    store: Arc<dyn Store>,
+    config: Arc<Config>,
+    events: Arc<DashMap<JobId, broadcast::Sender<String>>>,
+}
+
+impl JobQueue {
+    /// Create a queue that runs at most `max_concurrent` generations at once,
+    /// mirroring each job's output into `store` and following `config`.
+    pub fn new(max_concurrent: usize, store: Arc<dyn Store>, config: Arc<Config>) -> Self {
+        Self {
+            progress: Arc::new(DashMap::new()),
+            semaphore: Arc::new(Semaphore::new(max_concurrent)),
+            store,
+            config,
+            events: Arc::new(DashMap::new()),
+        }
+    }
+
+    /// Look up the current progress for a job.
+    pub fn get(&self, id: &JobId) -> Option<Progress> {
+        self.progress.get(id).map(|entry| entry.clone())
+    }
+
+    /// Subscribe to a job's live progress deltas, if the job exists.
+    pub fn subscribe(&self, id: &JobId) -> Option<broadcast::Receiver<String>> {
+        self.events.get(id).map(|tx| tx.subscribe())
+    }
+
+    /// Submit a new prompt, returning its job id immediately. The job runs
+    /// in a background task once a concurrency permit becomes available.
+    pub fn submit(&self, user_input: String) -> JobId {
+        let id = Uuid::new_v4();
+        self.progress.insert(id, Progress::default());
+
+        let (events_tx, _events_rx) = broadcast::channel(EVENTS_CHANNEL_CAPACITY);
+        self.events.insert(id, events_tx.clone());
+
+        let job = Job {
+            id,
+            user_input,
+            target_dir: job_dir(&id),
+        };
+
+        let queue = self.clone();
+        actix_web::rt::spawn(async move {
+            // Bound the number of generations running concurrently; jobs
+            // queue up here until a slot frees.
+            let permit = queue
+                .semaphore
+                .acquire()
+                .await
+                .expect("semaphore is never closed");
+            crate::run_main_loop(
+                job,
+                queue.progress.clone(),
+                queue.store.clone(),
+                queue.config.clone(),
+                events_tx,
+            )
+            .await;
+
+            // Release the concurrency slot as soon as the generation work is
+            // done; only stale-client cleanup is left, which shouldn't hold
+            // up the next queued job.
+            drop(permit);
+
+            // Reap this job's state after a grace period instead of
+            // leaking one `Progress` entry and one broadcast sender per
+            // submitted job for the life of the server.
+            tokio::time::sleep(COMPLETED_JOB_RETENTION).await;
+            queue.progress.remove(&id);
+            queue.events.remove(&id);
+        });
+
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::store::FileStore;
+
+    #[test]
+    fn job_dir_is_scoped_under_flask_app_by_id() {
+        let id = Uuid::nil();
+        assert_eq!(
+            job_dir(&id),
+            PathBuf::from("flask_app").join(id.to_string())
+        );
+    }
+
+    #[test]
+    fn unknown_job_id_has_no_progress_or_subscription() {
+        let config = Arc::new(Config {
+            model: "llama3.2".to_string(),
+            ollama_url: "http://localhost:11434".to_string(),
+            bind: "0.0.0.0:8080".to_string(),
+            max_iterations: 50,
+            components: Vec::new(),
+            step_deadline_secs: 120,
+            job_deadline_secs: 1800,
+            store_backend: crate::config::StoreBackend::Local,
+            s3: crate::config::S3Config::default(),
+        });
+        let store: Arc<dyn Store> = Arc::new(FileStore::new("."));
+        let queue = JobQueue::new(1, store, config);
+
+        let unknown = Uuid::new_v4();
+        assert!(queue.get(&unknown).is_none());
+        assert!(queue.subscribe(&unknown).is_none());
+    }
+}
@@ -0,0 +1,143 @@
+//! Git-backed version history for a generated project.
+//!
+//! Borrows the git-backed workflow from the Hugotator CMS: every component
+//! file the generator writes is staged and committed individually, so a user
+//! can see the diff between iterations or hard-reset the project back to an
+//! earlier step. A reset restores the whole working tree to that commit, so
+//! every file written in later steps is rolled back with it.
+
+use git2::{Oid, Repository, ResetType, Signature};
+use std::path::Path;
+
+const COMMIT_AUTHOR: &str = "rust_coding_agent";
+const COMMIT_EMAIL: &str = "agent@localhost";
+
+/// A single commit recorded against a generated project.
+pub struct CommitInfo {
+    pub sha: String,
+    pub message: String,
+}
+
+/// Thin wrapper around a project's git repository.
+pub struct ProjectRepo {
+    repo: Repository,
+}
+
+impl ProjectRepo {
+    /// Open the repository at `dir`, initializing one if it doesn't exist yet.
+    pub fn open_or_init(dir: &Path) -> Result<Self, git2::Error> {
+        let repo = match Repository::open(dir) {
+            Ok(repo) => repo,
+            Err(_) => Repository::init(dir)?,
+        };
+        Ok(Self { repo })
+    }
+
+    /// Stage `relative_path` (relative to the repo root) and commit it,
+    /// returning the new commit's SHA.
+    pub fn commit_file(&self, relative_path: &Path, message: &str) -> Result<String, git2::Error> {
+        let mut index = self.repo.index()?;
+        index.add_path(relative_path)?;
+        index.write()?;
+
+        let tree_oid = index.write_tree()?;
+        let tree = self.repo.find_tree(tree_oid)?;
+        let signature = Signature::now(COMMIT_AUTHOR, COMMIT_EMAIL)?;
+
+        let parent_commit = self.repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent_commit.iter().collect();
+
+        let oid = self.repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )?;
+        Ok(oid.to_string())
+    }
+
+    /// List commits on the current branch, newest first.
+    pub fn list_commits(&self) -> Result<Vec<CommitInfo>, git2::Error> {
+        let mut revwalk = self.repo.revwalk()?;
+        if revwalk.push_head().is_err() {
+            // No commits yet.
+            return Ok(Vec::new());
+        }
+
+        revwalk
+            .map(|oid| {
+                let oid = oid?;
+                let commit = self.repo.find_commit(oid)?;
+                Ok(CommitInfo {
+                    sha: oid.to_string(),
+                    message: commit.message().unwrap_or("").to_string(),
+                })
+            })
+            .collect()
+    }
+
+    /// Hard-reset the working tree back to `sha`, discarding any changes made since.
+    pub fn reset_hard(&self, sha: &str) -> Result<(), git2::Error> {
+        let oid = Oid::from_str(sha)?;
+        let commit = self.repo.find_commit(oid)?;
+        self.repo.reset(commit.as_object(), ResetType::Hard, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn tempdir() -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("repo_test_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn commit_file_creates_a_commit_and_list_commits_sees_it() {
+        let dir = tempdir();
+        let repo = ProjectRepo::open_or_init(&dir).unwrap();
+        fs::write(dir.join("app.py"), "print('hi')").unwrap();
+
+        let sha = repo.commit_file(Path::new("app.py"), "add app.py").unwrap();
+
+        let commits = repo.list_commits().unwrap();
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].sha, sha);
+        assert_eq!(commits[0].message, "add app.py");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn list_commits_is_empty_for_a_fresh_repo() {
+        let dir = tempdir();
+        let repo = ProjectRepo::open_or_init(&dir).unwrap();
+
+        assert!(repo.list_commits().unwrap().is_empty());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn reset_hard_restores_a_file_changed_in_a_later_commit() {
+        let dir = tempdir();
+        let repo = ProjectRepo::open_or_init(&dir).unwrap();
+
+        fs::write(dir.join("app.py"), "v1").unwrap();
+        let first_sha = repo.commit_file(Path::new("app.py"), "v1").unwrap();
+
+        fs::write(dir.join("app.py"), "v2").unwrap();
+        repo.commit_file(Path::new("app.py"), "v2").unwrap();
+        assert_eq!(fs::read_to_string(dir.join("app.py")).unwrap(), "v2");
+
+        repo.reset_hard(&first_sha).unwrap();
+        assert_eq!(fs::read_to_string(dir.join("app.py")).unwrap(), "v1");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,210 @@
+//! Pluggable storage backend so a generated project's output goes through
+//! one narrow interface instead of scattered `fs::write` calls.
+//!
+//! Following pict-rs's `Store` trait abstraction (file store vs. object
+//! store), callers write and read by a repo-relative [`Path`] and don't
+//! need to know where that path actually lives. [`FileStore`] is always
+//! available; [`ObjectStore`] backs onto a real S3-compatible bucket via
+//! `aws-sdk-s3` and only compiles in behind the `s3` feature, since that
+//! dependency is heavy and most deployments never need it. Select the
+//! backend with `Config::store_backend` (`--store-backend s3` plus
+//! `--s3-bucket`/`--s3-region`/`--s3-endpoint`/`--s3-access-key`/
+//! `--s3-secret-key`).
+
+use async_trait::async_trait;
+use std::{fmt, io, path::Path};
+
+#[derive(Debug)]
+pub enum StoreError {
+    Io(io::Error),
+    Object(String),
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoreError::Io(e) => write!(f, "io error: {}", e),
+            StoreError::Object(msg) => write!(f, "object store error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+impl From<io::Error> for StoreError {
+    fn from(e: io::Error) -> Self {
+        StoreError::Io(e)
+    }
+}
+
+/// Where a generated project's files end up.
+#[async_trait]
+pub trait Store: Send + Sync {
+    async fn write(&self, path: &Path, bytes: &[u8]) -> Result<(), StoreError>;
+    async fn read(&self, path: &Path) -> Result<Vec<u8>, StoreError>;
+    async fn mkdir(&self, path: &Path) -> Result<(), StoreError>;
+}
+
+/// Writes to the local filesystem, rooted at `root`. This preserves the
+/// tool's original behavior.
+pub struct FileStore {
+    root: std::path::PathBuf,
+}
+
+impl FileStore {
+    pub fn new(root: impl Into<std::path::PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn resolve(&self, path: &Path) -> std::path::PathBuf {
+        self.root.join(path)
+    }
+}
+
+#[async_trait]
+impl Store for FileStore {
+    async fn write(&self, path: &Path, bytes: &[u8]) -> Result<(), StoreError> {
+        let full_path = self.resolve(path);
+        if let Some(parent) = full_path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(full_path, bytes).await?;
+        Ok(())
+    }
+
+    async fn read(&self, path: &Path) -> Result<Vec<u8>, StoreError> {
+        Ok(tokio::fs::read(self.resolve(path)).await?)
+    }
+
+    async fn mkdir(&self, path: &Path) -> Result<(), StoreError> {
+        tokio::fs::create_dir_all(self.resolve(path)).await?;
+        Ok(())
+    }
+}
+
+/// Credentials and location of an S3-compatible bucket.
+#[cfg(feature = "s3")]
+pub struct ObjectStoreConfig {
+    pub bucket: String,
+    pub region: String,
+    pub endpoint: Option<String>,
+    pub access_key: String,
+    pub secret_key: String,
+}
+
+/// Writes to an S3-compatible bucket, keyed by the given path, via the real
+/// `aws-sdk-s3` client (not a stub) so a "successful" write actually landed
+/// in the bucket.
+#[cfg(feature = "s3")]
+pub struct ObjectStore {
+    client: aws_sdk_s3::Client,
+    bucket: String,
+}
+
+#[cfg(feature = "s3")]
+impl ObjectStore {
+    pub fn new(config: ObjectStoreConfig) -> Self {
+        use aws_sdk_s3::config::{Builder as S3ConfigBuilder, Credentials, Region};
+
+        let credentials = Credentials::new(
+            config.access_key,
+            config.secret_key,
+            None,
+            None,
+            "rust_coding_agent",
+        );
+        let mut builder = S3ConfigBuilder::new()
+            .region(Region::new(config.region))
+            .credentials_provider(credentials)
+            .behavior_version_latest();
+        if let Some(endpoint) = config.endpoint {
+            builder = builder.endpoint_url(endpoint);
+        }
+
+        Self {
+            client: aws_sdk_s3::Client::from_conf(builder.build()),
+            bucket: config.bucket,
+        }
+    }
+
+    fn key(&self, path: &Path) -> String {
+        path.to_string_lossy().replace('\\', "/")
+    }
+}
+
+#[cfg(feature = "s3")]
+#[async_trait]
+impl Store for ObjectStore {
+    async fn write(&self, path: &Path, bytes: &[u8]) -> Result<(), StoreError> {
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(self.key(path))
+            .body(aws_sdk_s3::primitives::ByteStream::from(bytes.to_vec()))
+            .send()
+            .await
+            .map_err(|e| StoreError::Object(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn read(&self, path: &Path) -> Result<Vec<u8>, StoreError> {
+        let output = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(self.key(path))
+            .send()
+            .await
+            .map_err(|e| StoreError::Object(e.to_string()))?;
+        let bytes = output
+            .body
+            .collect()
+            .await
+            .map_err(|e| StoreError::Object(e.to_string()))?;
+        Ok(bytes.into_bytes().to_vec())
+    }
+
+    async fn mkdir(&self, _path: &Path) -> Result<(), StoreError> {
+        // Object storage has no directories; keys are created implicitly on write.
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn file_store_write_then_read_roundtrips_bytes() {
+        let root = std::env::temp_dir().join(format!("store_test_{}", uuid::Uuid::new_v4()));
+        let store = FileStore::new(&root);
+
+        store
+            .write(Path::new("nested/app.py"), b"print('hi')")
+            .await
+            .unwrap();
+        let bytes = store.read(Path::new("nested/app.py")).await.unwrap();
+
+        assert_eq!(bytes, b"print('hi')");
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn file_store_read_of_missing_file_is_an_error() {
+        let root = std::env::temp_dir().join(format!("store_test_{}", uuid::Uuid::new_v4()));
+        let store = FileStore::new(&root);
+
+        assert!(store.read(Path::new("missing.py")).await.is_err());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn file_store_mkdir_creates_nested_directories() {
+        let root = std::env::temp_dir().join(format!("store_test_{}", uuid::Uuid::new_v4()));
+        let store = FileStore::new(&root);
+
+        store.mkdir(Path::new("a/b/c")).await.unwrap();
+        assert!(root.join("a/b/c").is_dir());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,94 @@
+//! Validates generated Python files and builds repair prompts for the LLM.
+//!
+//! Analogous to pict-rs's `validate` module gating output by correctness:
+//! nothing gets persisted until it either compiles or has exhausted its
+//! repair attempts.
+
+use std::path::Path;
+use tokio::process::Command;
+
+/// A `python3 -m py_compile` failure, with the compiler's stderr captured verbatim.
+pub struct CompileError {
+    pub stderr: String,
+}
+
+/// Syntax-checks a Python file by shelling out to `python3 -m py_compile`.
+///
+/// Uses `tokio::process::Command` rather than `std::process::Command` so
+/// awaiting the child process doesn't block the worker thread running this
+/// job's generation loop, letting other jobs keep making progress on it.
+pub async fn check_python_syntax(path: &Path) -> Result<(), CompileError> {
+    let output = Command::new("python3")
+        .arg("-m")
+        .arg("py_compile")
+        .arg(path)
+        .output()
+        .await;
+
+    match output {
+        Ok(output) if output.status.success() => Ok(()),
+        Ok(output) => Err(CompileError {
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        }),
+        Err(e) => Err(CompileError {
+            stderr: format!("failed to invoke python3: {}", e),
+        }),
+    }
+}
+
+/// Builds a repair prompt quoting the original component prompt, the
+/// failing code, and the exact compiler error, so the model can self-correct.
+pub fn repair_prompt(component_prompt: &str, failing_code: &str, error: &str) -> String {
+    format!(
+        "{component_prompt}\n\n\
+        The code you previously generated for this file does not compile. Here is the code:\n\n\
+        {failing_code}\n\n\
+        It failed with this error:\n\n\
+        {error}\n\n\
+        Fix the code so it compiles. Provide only the corrected, complete file content, \
+        without any explanations or Markdown formatting.",
+        component_prompt = component_prompt,
+        failing_code = failing_code,
+        error = error,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn repair_prompt_quotes_the_component_prompt_code_and_error_verbatim() {
+        let prompt = repair_prompt(
+            "Create routes.py with a /health endpoint.",
+            "def health()\n    return 'ok'",
+            "SyntaxError: expected ':'",
+        );
+
+        assert!(prompt.contains("Create routes.py with a /health endpoint."));
+        assert!(prompt.contains("def health()\n    return 'ok'"));
+        assert!(prompt.contains("SyntaxError: expected ':'"));
+    }
+
+    #[tokio::test]
+    async fn check_python_syntax_accepts_valid_code() {
+        let dir = std::env::temp_dir().join("validate_test_valid.py");
+        fs::write(&dir, "def ok():\n    return 1\n").unwrap();
+
+        assert!(check_python_syntax(&dir).await.is_ok());
+
+        let _ = fs::remove_file(&dir);
+    }
+
+    #[tokio::test]
+    async fn check_python_syntax_reports_the_compiler_error_on_bad_code() {
+        let dir = std::env::temp_dir().join("validate_test_broken.py");
+        fs::write(&dir, "def broken(\n").unwrap();
+
+        let err = check_python_syntax(&dir).await.unwrap_err();
+        assert!(!err.stderr.is_empty());
+
+        let _ = fs::remove_file(&dir);
+    }
+}